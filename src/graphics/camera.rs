@@ -0,0 +1,123 @@
+//! Functions and types relating to cameras.
+
+use glm::{Mat4, Vec2};
+
+/// A 2D camera, used to scroll, zoom and rotate the view without having to manually offset
+/// every [`DrawParams::position`](struct.DrawParams.html#method.position).
+///
+/// Call [`as_matrix`](#method.as_matrix) and pass the result to
+/// [`graphics::set_transform_matrix`](fn.set_transform_matrix.html) whenever the camera moves.
+pub struct Camera {
+    /// The world-space point that the camera is centered on.
+    pub position: Vec2,
+
+    /// The camera's zoom level. Values greater than `1.0` zoom in, values between `0.0` and
+    /// `1.0` zoom out.
+    pub zoom: f32,
+
+    /// The camera's rotation, in radians.
+    pub rotation: f32,
+
+    viewport_size: Vec2,
+}
+
+impl Camera {
+    /// Creates a new camera for a viewport of the given size - usually
+    /// `(graphics::get_width(ctx), graphics::get_height(ctx))`.
+    pub fn new(viewport_width: i32, viewport_height: i32) -> Camera {
+        Camera {
+            position: Vec2::new(0.0, 0.0),
+            zoom: 1.0,
+            rotation: 0.0,
+            viewport_size: Vec2::new(viewport_width as f32, viewport_height as f32),
+        }
+    }
+
+    /// Builds the view matrix for the camera's current position, zoom and rotation.
+    ///
+    /// Pass this to [`graphics::set_transform_matrix`](fn.set_transform_matrix.html) before
+    /// drawing the world that the camera should apply to.
+    pub fn as_matrix(&self) -> Mat4 {
+        let (sin, cos) = self.rotation.sin_cos();
+        let zoom = self.zoom;
+
+        let center_x = self.viewport_size.x / 2.0;
+        let center_y = self.viewport_size.y / 2.0;
+
+        let tx = center_x - zoom * (cos * self.position.x - sin * self.position.y);
+        let ty = center_y - zoom * (sin * self.position.x + cos * self.position.y);
+
+        Mat4::from([
+            [zoom * cos, zoom * sin, 0.0, 0.0],
+            [-zoom * sin, zoom * cos, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [tx, ty, 0.0, 1.0],
+        ])
+    }
+
+    /// Converts a point in screen space (e.g. the mouse position) into the equivalent point in
+    /// world space, taking the camera's current position/zoom/rotation into account.
+    pub fn screen_to_world(&self, position: Vec2) -> Vec2 {
+        let (sin, cos) = self.rotation.sin_cos();
+
+        let relative = Vec2::new(
+            (position.x - self.viewport_size.x / 2.0) / self.zoom,
+            (position.y - self.viewport_size.y / 2.0) / self.zoom,
+        );
+
+        Vec2::new(
+            relative.x * cos + relative.y * sin + self.position.x,
+            relative.y * cos - relative.x * sin + self.position.y,
+        )
+    }
+
+    /// Converts a point in world space into the equivalent point in screen space, taking the
+    /// camera's current position/zoom/rotation into account.
+    pub fn world_to_screen(&self, position: Vec2) -> Vec2 {
+        let (sin, cos) = self.rotation.sin_cos();
+
+        let relative = Vec2::new(position.x - self.position.x, position.y - self.position.y);
+
+        Vec2::new(
+            self.zoom * (relative.x * cos - relative.y * sin) + self.viewport_size.x / 2.0,
+            self.zoom * (relative.x * sin + relative.y * cos) + self.viewport_size.y / 2.0,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Camera;
+    use glm::Vec2;
+
+    fn assert_approx_eq(a: Vec2, b: Vec2) {
+        assert!((a.x - b.x).abs() < 0.001, "{:?} != {:?}", a, b);
+        assert!((a.y - b.y).abs() < 0.001, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn screen_to_world_and_back_round_trips_with_default_camera() {
+        let camera = Camera::new(800, 600);
+        let screen = Vec2::new(123.0, 456.0);
+
+        let world = camera.screen_to_world(screen);
+        let round_tripped = camera.world_to_screen(world);
+
+        assert_approx_eq(round_tripped, screen);
+    }
+
+    #[test]
+    fn screen_to_world_and_back_round_trips_with_moved_zoomed_rotated_camera() {
+        let mut camera = Camera::new(800, 600);
+        camera.position = Vec2::new(100.0, -50.0);
+        camera.zoom = 2.5;
+        camera.rotation = 0.7;
+
+        let screen = Vec2::new(50.0, 300.0);
+
+        let world = camera.screen_to_world(screen);
+        let round_tripped = camera.world_to_screen(world);
+
+        assert_approx_eq(round_tripped, screen);
+    }
+}
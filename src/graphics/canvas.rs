@@ -0,0 +1,79 @@
+//! Functions and types relating to rendering to off-screen targets.
+
+use graphics::opengl::GLFramebuffer;
+use graphics::texture::Texture;
+use graphics::{push_sprite, set_texture, DrawParams, Drawable, Rectangle};
+use Context;
+
+/// A texture that can be used as a render target, allowing for off-screen rendering
+/// and post-processing effects.
+///
+/// Once created, a `Canvas` can be activated via [`graphics::set_canvas`](fn.set_canvas.html),
+/// after which any drawing calls will render into it instead of the screen. It can then be
+/// drawn to the screen (or another `Canvas`) like any other texture, as it implements
+/// [`Drawable`](trait.Drawable.html).
+pub struct Canvas {
+    pub(crate) framebuffer: GLFramebuffer,
+    texture: Texture,
+    pub(crate) width: i32,
+    pub(crate) height: i32,
+}
+
+impl Canvas {
+    /// Creates a new `Canvas`, with the given width and height.
+    pub fn new(ctx: &mut Context, width: i32, height: i32) -> Canvas {
+        let framebuffer = ctx.gl.new_framebuffer();
+        let texture = Texture::from_handle(ctx.gl.new_texture(width, height));
+
+        ctx.gl
+            .attach_texture_to_framebuffer(&framebuffer, &texture.handle, false);
+
+        Canvas {
+            framebuffer,
+            texture,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the underlying texture that this canvas renders into.
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+}
+
+impl Drawable for Canvas {
+    fn draw<T: Into<DrawParams>>(&self, ctx: &mut Context, params: T) {
+        // A canvas' texture is backed by an FBO, which OpenGL stores bottom-up - flip the V
+        // co-ordinates to compensate, the same way `present` does for the main framebuffer.
+        // We can't just delegate to `self.texture.draw`, as it has no knowledge of this flip.
+        let params = params.into();
+
+        let (width, height) = (self.width as f32, self.height as f32);
+        let clip = params.clip.unwrap_or_else(|| Rectangle::new(0.0, 0.0, width, height));
+
+        set_texture(ctx, &self.texture);
+
+        let draw_width = clip.width * params.scale.x;
+        let draw_height = clip.height * params.scale.y;
+
+        let x = params.position.x - params.origin.x * params.scale.x;
+        let y = params.position.y - params.origin.y * params.scale.y;
+
+        let u1 = clip.x / width;
+        let u2 = (clip.x + clip.width) / width;
+        let v1 = 1.0 - clip.y / height;
+        let v2 = 1.0 - (clip.y + clip.height) / height;
+
+        push_sprite(
+            ctx,
+            [
+                (x, y, u1, v1),
+                (x, y + draw_height, u1, v2),
+                (x + draw_width, y + draw_height, u2, v2),
+                (x + draw_width, y, u2, v1),
+            ],
+            params.color,
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Functions and types relating to drawing primitives and meshes.
+
+use glm::Vec2;
+
+use graphics::opengl::{BufferUsage, GLIndexBuffer, GLVertexBuffer};
+use graphics::{flush, DrawParams, Drawable, Rectangle, VERTEX_STRIDE};
+use Context;
+
+/// Whether a shape should be drawn filled in, or as an outline of the given thickness.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ShapeMode {
+    /// Fill the interior of the shape.
+    Fill,
+
+    /// Draw only the outline of the shape, at the given thickness.
+    Stroke(f32),
+}
+
+/// Accumulates vertex/index data for an arbitrary triangle mesh.
+///
+/// Unlike [`Texture`](../struct.Texture.html), a `Mesh` doesn't flow through the shared quad
+/// batcher - it has its own index data (since its triangle count isn't known ahead of time),
+/// so building one allocates its own vertex/index buffers on the GPU.
+#[derive(Clone, Default)]
+pub struct MeshBuilder {
+    vertices: Vec<f32>,
+    indices: Vec<u32>,
+}
+
+impl MeshBuilder {
+    /// Creates a new, empty `MeshBuilder`.
+    pub fn new() -> MeshBuilder {
+        MeshBuilder {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+        }
+    }
+
+    /// Adds a filled or stroked rectangle to the mesh.
+    pub fn rectangle(&mut self, mode: ShapeMode, rect: Rectangle) -> &mut MeshBuilder {
+        let points = [
+            Vec2::new(rect.x, rect.y),
+            Vec2::new(rect.x + rect.width, rect.y),
+            Vec2::new(rect.x + rect.width, rect.y + rect.height),
+            Vec2::new(rect.x, rect.y + rect.height),
+        ];
+
+        self.polygon(mode, &points)
+    }
+
+    /// Adds a filled or stroked circle to the mesh, approximated using the given number of segments.
+    pub fn circle(
+        &mut self,
+        mode: ShapeMode,
+        center: Vec2,
+        radius: f32,
+        segments: u32,
+    ) -> &mut MeshBuilder {
+        let points: Vec<Vec2> = (0..segments)
+            .map(|i| {
+                let theta = (i as f32 / segments as f32) * ::std::f32::consts::PI * 2.0;
+                Vec2::new(center.x + theta.cos() * radius, center.y + theta.sin() * radius)
+            })
+            .collect();
+
+        self.polygon(mode, &points)
+    }
+
+    /// Adds a closed convex polygon to the mesh, fan-triangulating it if filled.
+    ///
+    /// Does nothing if fewer than 3 points are given, since that isn't a polygon.
+    fn polygon(&mut self, mode: ShapeMode, points: &[Vec2]) -> &mut MeshBuilder {
+        if points.len() < 3 {
+            return self;
+        }
+
+        match mode {
+            ShapeMode::Fill => {
+                let base = (self.vertices.len() / VERTEX_STRIDE) as u32;
+
+                for point in points {
+                    self.push_vertex(*point);
+                }
+
+                for i in 1..points.len() as u32 - 1 {
+                    self.indices.push(base);
+                    self.indices.push(base + i);
+                    self.indices.push(base + i + 1);
+                }
+            }
+            ShapeMode::Stroke(thickness) => {
+                let mut closed = points.to_vec();
+                closed.push(points[0]);
+                self.polyline(&closed, thickness);
+            }
+        }
+
+        self
+    }
+
+    /// Adds a (non-closed) polyline to the mesh, expanding each segment into a quad of the
+    /// given thickness.
+    pub fn polyline(&mut self, points: &[Vec2], thickness: f32) -> &mut MeshBuilder {
+        for segment in points.windows(2) {
+            let (p0, p1) = (segment[0], segment[1]);
+            let direction = normalize(p1 - p0);
+            let normal = Vec2::new(-direction.y, direction.x) * (thickness / 2.0);
+
+            let base = (self.vertices.len() / VERTEX_STRIDE) as u32;
+
+            self.push_vertex(p0 + normal);
+            self.push_vertex(p0 - normal);
+            self.push_vertex(p1 - normal);
+            self.push_vertex(p1 + normal);
+
+            self.indices.push(base);
+            self.indices.push(base + 1);
+            self.indices.push(base + 2);
+            self.indices.push(base + 2);
+            self.indices.push(base + 3);
+            self.indices.push(base);
+        }
+
+        self
+    }
+
+    fn push_vertex(&mut self, position: Vec2) {
+        self.vertices.push(position.x);
+        self.vertices.push(position.y);
+        self.vertices.push(0.0);
+        self.vertices.push(0.0);
+        self.vertices.push(1.0);
+        self.vertices.push(1.0);
+        self.vertices.push(1.0);
+        self.vertices.push(1.0);
+    }
+
+    /// Uploads the accumulated geometry to the GPU, producing a drawable [`Mesh`](struct.Mesh.html).
+    pub fn build(&self, ctx: &mut Context) -> Mesh {
+        let vertex_buffer =
+            ctx.gl
+                .new_vertex_buffer(self.vertices.len(), VERTEX_STRIDE, BufferUsage::DynamicDraw);
+
+        ctx.gl.set_vertex_buffer_attribute(&vertex_buffer, 0, 4, 0);
+        ctx.gl.set_vertex_buffer_attribute(&vertex_buffer, 1, 3, 4);
+
+        let index_buffer = ctx
+            .gl
+            .new_index_buffer(self.indices.len(), BufferUsage::StaticDraw);
+        ctx.gl.set_index_buffer_data(&index_buffer, &self.indices, 0);
+
+        Mesh {
+            vertex_buffer,
+            index_buffer,
+            index_count: self.indices.len(),
+            local_vertices: self.vertices.clone(),
+        }
+    }
+}
+
+fn normalize(v: Vec2) -> Vec2 {
+    let length = (v.x * v.x + v.y * v.y).sqrt();
+    Vec2::new(v.x / length, v.y / length)
+}
+
+/// A triangle mesh that has been uploaded to the GPU, ready to be drawn.
+///
+/// Built via [`MeshBuilder::build`](struct.MeshBuilder.html#method.build).
+pub struct Mesh {
+    vertex_buffer: GLVertexBuffer,
+    index_buffer: GLIndexBuffer,
+    index_count: usize,
+    local_vertices: Vec<f32>,
+}
+
+impl Drawable for Mesh {
+    fn draw<T: Into<DrawParams>>(&self, ctx: &mut Context, params: T) {
+        // Meshes are drawn outside of the shared quad batch, so make sure anything already
+        // queued is sent to the hardware first, to preserve draw order.
+        flush(ctx);
+
+        let params = params.into();
+
+        let transformed: Vec<f32> = self
+            .local_vertices
+            .chunks(VERTEX_STRIDE)
+            .flat_map(|vertex| {
+                vec![
+                    (vertex[0] - params.origin.x) * params.scale.x + params.position.x,
+                    (vertex[1] - params.origin.y) * params.scale.y + params.position.y,
+                    vertex[2],
+                    vertex[3],
+                    vertex[4] * params.color.r,
+                    vertex[5] * params.color.g,
+                    vertex[6] * params.color.b,
+                    vertex[7] * params.color.a,
+                ]
+            })
+            .collect();
+
+        ctx.gl
+            .set_vertex_buffer_data(&self.vertex_buffer, &transformed, 0);
+
+        let shader = ctx
+            .graphics
+            .shader
+            .as_ref()
+            .unwrap_or(&ctx.graphics.default_shader);
+
+        let base_projection = ctx
+            .graphics
+            .canvas_projection
+            .unwrap_or(ctx.graphics.internal_projection);
+
+        let projection = base_projection * ctx.graphics.transform_matrix;
+
+        ctx.gl.set_uniform(&shader.handle, "projection", &projection);
+
+        ctx.gl.draw(
+            &self.vertex_buffer,
+            &self.index_buffer,
+            &shader.handle,
+            &ctx.graphics.white_texture.handle,
+            self.index_count,
+        );
+    }
+}
@@ -6,22 +6,33 @@
 //! rendering.
 
 pub mod animation;
+pub mod camera;
+pub mod canvas;
 pub mod color;
+pub mod mesh;
 pub(crate) mod opengl;
 pub mod shader;
+pub mod text;
 pub mod texture;
 
 pub use self::animation::Animation;
+pub use self::camera::Camera;
+pub use self::canvas::Canvas;
 pub use self::color::Color;
+pub use self::mesh::{Mesh, MeshBuilder, ShapeMode};
 pub use self::shader::Shader;
+pub use self::text::{Font, Text};
 pub use self::texture::Texture;
 
 use glm::{Mat4, Vec2};
 use graphics::opengl::{BufferUsage, GLDevice, GLFramebuffer, GLIndexBuffer, GLVertexBuffer};
 use Context;
 
+/// The number of sprites that can be batched together before a flush is forced.
+///
+/// This is a compile-time constant - it cannot be changed when the context is created.
 const SPRITE_CAPACITY: usize = 1024;
-const VERTEX_STRIDE: usize = 8;
+pub(crate) const VERTEX_STRIDE: usize = 8;
 const INDEX_STRIDE: usize = 6;
 const INDEX_ARRAY: [u32; INDEX_STRIDE] = [0, 1, 2, 2, 3, 0];
 const DEFAULT_VERTEX_SHADER: &str = include_str!("../resources/shader.vert");
@@ -32,13 +43,17 @@ pub(crate) struct GraphicsContext {
     index_buffer: GLIndexBuffer,
     framebuffer: GLFramebuffer,
     framebuffer_texture: Texture,
+    white_texture: Texture,
 
     texture: Option<Texture>,
     shader: Option<Shader>,
     default_shader: Shader,
+    blend_mode: BlendMode,
 
     internal_projection: Mat4,
     window_projection: Mat4,
+    canvas_projection: Option<Mat4>,
+    transform_matrix: Mat4,
 
     vertices: Vec<f32>,
     sprite_count: usize,
@@ -48,6 +63,7 @@ pub(crate) struct GraphicsContext {
     internal_height: i32,
     window_width: i32,
     window_height: i32,
+    scaling_mode: ScalingMode,
     letterbox: Rectangle,
 }
 
@@ -71,6 +87,9 @@ impl GraphicsContext {
         device.attach_texture_to_framebuffer(&framebuffer, &framebuffer_texture.handle, false);
         device.set_viewport(0, 0, internal_width, internal_height);
 
+        let white_texture = Texture::from_handle(device.new_texture(1, 1));
+        device.set_texture_data(&white_texture.handle, 0, 0, 1, 1, &[255, 255, 255, 255]);
+
         let indices: Vec<u32> = INDEX_ARRAY
             .iter()
             .cycle()
@@ -97,15 +116,19 @@ impl GraphicsContext {
             device.compile_program(DEFAULT_VERTEX_SHADER, DEFAULT_FRAGMENT_SHADER),
         );
 
+        device.set_blend_mode(BlendMode::Alpha);
+
         GraphicsContext {
             vertex_buffer,
             index_buffer,
             framebuffer,
             framebuffer_texture,
+            white_texture,
 
             texture: None,
             shader: None,
             default_shader,
+            blend_mode: BlendMode::Alpha,
 
             internal_projection: ortho(
                 0.0,
@@ -123,6 +146,8 @@ impl GraphicsContext {
                 -1.0,
                 1.0,
             ),
+            canvas_projection: None,
+            transform_matrix: identity(),
 
             vertices: Vec::with_capacity(SPRITE_CAPACITY * 4 * VERTEX_STRIDE),
             sprite_count: 0,
@@ -132,11 +157,42 @@ impl GraphicsContext {
             internal_height,
             window_width,
             window_height,
-            letterbox: letterbox(internal_width, internal_height, window_width, window_height),
+            scaling_mode: ScalingMode::CrispPixel,
+            letterbox: letterbox(
+                ScalingMode::CrispPixel,
+                internal_width,
+                internal_height,
+                window_width,
+                window_height,
+            ),
         }
     }
 }
 
+/// Describes how the internal canvas should be scaled up to fill the window.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ScalingMode {
+    /// The internal canvas is drawn at a 1:1 pixel ratio, and is not scaled to fit the window.
+    Fixed,
+
+    /// The internal canvas is stretched to fill the window exactly, ignoring aspect ratio.
+    Stretch,
+
+    /// The internal canvas is scaled up as much as possible while still fitting entirely
+    /// within the window, preserving aspect ratio. This can result in black bars if the
+    /// aspect ratios don't match.
+    ShowAll,
+
+    /// The internal canvas is scaled up as much as possible while still covering the whole
+    /// window, preserving aspect ratio. This can crop some of the canvas off-screen.
+    Crop,
+
+    /// The internal canvas is scaled up by the largest whole number that still fits within
+    /// the window, preserving pixel-perfect rendering. The scale is never less than `1`, so a
+    /// window smaller than the internal canvas crops it. This is the default.
+    CrispPixel,
+}
+
 /// A rectangle of `f32`s.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Rectangle {
@@ -370,6 +426,23 @@ pub(crate) fn push_vertex(ctx: &mut Context, x: f32, y: f32, u: f32, v: f32, col
     ctx.graphics.vertices.push(color.a);
 }
 
+/// Pushes the four corners of a textured quad onto the current batch, as `(x, y, u, v)` tuples.
+///
+/// This is the single choke point that every `Drawable` implementation should funnel through -
+/// it automatically [`flush`](fn.flush.html)es the batch first if the sprite would overflow the
+/// pre-sized vertex/index buffers, so callers don't need to think about `capacity` themselves.
+pub(crate) fn push_sprite(ctx: &mut Context, corners: [(f32, f32, f32, f32); 4], color: Color) {
+    if ctx.graphics.sprite_count >= ctx.graphics.capacity {
+        flush(ctx);
+    }
+
+    for (x, y, u, v) in &corners {
+        push_vertex(ctx, *x, *y, *u, *v, color);
+    }
+
+    ctx.graphics.sprite_count += 1;
+}
+
 /// Draws an object to the currently enabled render target.
 ///
 /// This function simply calls [`draw`](trait.Drawable.html#tymethod.draw) on the passed object - it is
@@ -379,6 +452,36 @@ pub fn draw<D: Drawable, P: Into<DrawParams>>(ctx: &mut Context, drawable: &D, p
     drawable.draw(ctx, params);
 }
 
+/// Describes how colors from drawn graphics should be blended with what's already in the
+/// render target.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BlendMode {
+    /// Standard alpha blending - `src.rgb * src.a + dst.rgb * (1.0 - src.a)`. This is the default.
+    Alpha,
+
+    /// Blending for graphics whose color channels have already been multiplied by their alpha.
+    Premultiplied,
+
+    /// Additive blending - `src.rgb * src.a + dst.rgb`. Useful for glows, lighting and particles.
+    Add,
+
+    /// Multiplicative blending - `src.rgb * dst.rgb`. Useful for shadows and tinting.
+    Multiply,
+}
+
+/// Sets the blend mode that is currently being used for rendering.
+///
+/// If the blend mode is different from the one that is currently in use, this will trigger a
+/// [`flush`](fn.flush.html) to the graphics hardware, so that graphics already queued are drawn
+/// with the old blend mode before it changes.
+pub fn set_blend_mode(ctx: &mut Context, mode: BlendMode) {
+    if ctx.graphics.blend_mode != mode {
+        flush(ctx);
+        ctx.graphics.blend_mode = mode;
+        ctx.gl.set_blend_mode(mode);
+    }
+}
+
 /// Sets the texture that is currently being used for rendering.
 ///
 /// If the texture is different from the one that is currently in use, this will trigger a
@@ -397,6 +500,44 @@ pub fn set_texture(ctx: &mut Context, texture: &Texture) {
     }
 }
 
+/// Sets the render target to draw to.
+///
+/// Passing `Some(canvas)` redirects all subsequent drawing onto that [`Canvas`](struct.Canvas.html),
+/// until `set_canvas` is called again - this makes it possible to render to a texture for later use,
+/// e.g. for post-processing effects or cached layers. Passing `None` switches back to the
+/// default, internal-resolution render target.
+///
+/// This will trigger a [`flush`](fn.flush.html), as the projection matrix and viewport need to
+/// change to match the new target.
+pub fn set_canvas(ctx: &mut Context, canvas: Option<&Canvas>) {
+    flush(ctx);
+
+    match canvas {
+        Some(canvas) => {
+            ctx.gl.bind_framebuffer(&canvas.framebuffer);
+            ctx.gl.set_viewport(0, 0, canvas.width, canvas.height);
+            ctx.graphics.canvas_projection = Some(ortho(
+                0.0,
+                canvas.width as f32,
+                canvas.height as f32,
+                0.0,
+                -1.0,
+                1.0,
+            ));
+        }
+        None => {
+            ctx.gl.bind_framebuffer(&ctx.graphics.framebuffer);
+            ctx.gl.set_viewport(
+                0,
+                0,
+                ctx.graphics.internal_width,
+                ctx.graphics.internal_height,
+            );
+            ctx.graphics.canvas_projection = None;
+        }
+    }
+}
+
 /// Sends queued data to the graphics hardware.
 ///
 /// You usually will not have to call this manually, as [`set_texture`](fn.set_texture.html) and
@@ -411,11 +552,14 @@ pub fn flush(ctx: &mut Context) {
             .as_ref()
             .unwrap_or(&ctx.graphics.default_shader);
 
-        ctx.gl.set_uniform(
-            &shader.handle,
-            "projection",
-            &ctx.graphics.internal_projection,
-        );
+        let base_projection = ctx
+            .graphics
+            .canvas_projection
+            .unwrap_or(ctx.graphics.internal_projection);
+
+        let projection = base_projection * ctx.graphics.transform_matrix;
+
+        ctx.gl.set_uniform(&shader.handle, "projection", &projection);
 
         ctx.gl
             .set_vertex_buffer_data(&ctx.graphics.vertex_buffer, &ctx.graphics.vertices, 0);
@@ -506,11 +650,43 @@ pub fn present(ctx: &mut Context) {
     );
 }
 
+/// Sets how the internal canvas should be scaled up to fill the window.
+///
+/// This recomputes the letterbox rectangle immediately, using the current window size.
+pub fn set_scaling_mode(ctx: &mut Context, mode: ScalingMode) {
+    ctx.graphics.scaling_mode = mode;
+    ctx.graphics.letterbox = letterbox(
+        mode,
+        ctx.graphics.internal_width,
+        ctx.graphics.internal_height,
+        ctx.graphics.window_width,
+        ctx.graphics.window_height,
+    );
+}
+
+/// Sets the transform matrix that is applied on top of the normal projection when drawing.
+///
+/// This can be used to implement a camera/view stack - see [`Camera`](struct.Camera.html) for a
+/// helper that builds a matrix from a position/zoom/rotation. Switching the matrix triggers a
+/// [`flush`](fn.flush.html), so that anything already queued is drawn with the old transform.
+pub fn set_transform_matrix(ctx: &mut Context, matrix: Mat4) {
+    flush(ctx);
+    ctx.graphics.transform_matrix = matrix;
+}
+
+/// Resets the transform matrix back to the identity matrix, so that drawing goes back to using
+/// raw internal-resolution co-ordinates.
+pub fn reset_transform(ctx: &mut Context) {
+    flush(ctx);
+    ctx.graphics.transform_matrix = identity();
+}
+
 pub(crate) fn set_window_size(ctx: &mut Context, width: i32, height: i32) {
     ctx.graphics.window_width = width;
     ctx.graphics.window_height = height;
     ctx.graphics.window_projection = ortho(0.0, width as f32, height as f32, 0.0, -1.0, 1.0);
     ctx.graphics.letterbox = letterbox(
+        ctx.graphics.scaling_mode,
         ctx.graphics.internal_width,
         ctx.graphics.internal_height,
         width,
@@ -519,28 +695,96 @@ pub(crate) fn set_window_size(ctx: &mut Context, width: i32, height: i32) {
 }
 
 fn letterbox(
+    scaling_mode: ScalingMode,
     internal_width: i32,
     internal_height: i32,
     window_width: i32,
     window_height: i32,
 ) -> Rectangle {
-    let scale_factor = if window_width <= window_height {
-        window_width / internal_width
-    } else {
-        window_height / internal_height
+    let internal_w = internal_width as f32;
+    let internal_h = internal_height as f32;
+    let window_w = window_width as f32;
+    let window_h = window_height as f32;
+
+    let (scale_x, scale_y) = match scaling_mode {
+        ScalingMode::Fixed => (1.0, 1.0),
+        ScalingMode::Stretch => (window_w / internal_w, window_h / internal_h),
+        ScalingMode::ShowAll => {
+            let scale = (window_w / internal_w).min(window_h / internal_h);
+            (scale, scale)
+        }
+        ScalingMode::Crop => {
+            let scale = (window_w / internal_w).max(window_h / internal_h);
+            (scale, scale)
+        }
+        ScalingMode::CrispPixel => {
+            let scale = (window_w / internal_w)
+                .min(window_h / internal_h)
+                .floor()
+                .max(1.0);
+            (scale, scale)
+        }
     };
 
-    let letterbox_width = internal_width * scale_factor;
-    let letterbox_height = internal_height * scale_factor;
-    let letterbox_x = (window_width - letterbox_width) / 2;
-    let letterbox_y = (window_height - letterbox_height) / 2;
-
-    Rectangle::new(
-        letterbox_x as f32,
-        letterbox_y as f32,
-        letterbox_width as f32,
-        letterbox_height as f32,
-    )
+    let letterbox_width = internal_w * scale_x;
+    let letterbox_height = internal_h * scale_y;
+    let letterbox_x = (window_w - letterbox_width) / 2.0;
+    let letterbox_y = (window_h - letterbox_height) / 2.0;
+
+    Rectangle::new(letterbox_x, letterbox_y, letterbox_width, letterbox_height)
+}
+
+#[cfg(test)]
+mod letterbox_tests {
+    use super::{letterbox, Rectangle, ScalingMode};
+
+    #[test]
+    fn fixed_ignores_window_size_but_is_still_centered() {
+        let rect = letterbox(ScalingMode::Fixed, 320, 240, 1920, 1080);
+        assert_eq!(rect, Rectangle::new(800.0, 420.0, 320.0, 240.0));
+    }
+
+    #[test]
+    fn stretch_fills_the_window_on_both_axes() {
+        let rect = letterbox(ScalingMode::Stretch, 320, 240, 1920, 1080);
+        assert_eq!(rect, Rectangle::new(0.0, 0.0, 1920.0, 1080.0));
+    }
+
+    #[test]
+    fn show_all_letterboxes_a_mismatched_aspect_ratio() {
+        // A 320x240 canvas in a 1920x240 window is limited by the vertical axis (scale 1.0,
+        // versus 6.0 horizontally), so it ends up pillarboxed rather than filling the width.
+        let rect = letterbox(ScalingMode::ShowAll, 320, 240, 1920, 240);
+        assert_eq!(rect, Rectangle::new(800.0, 0.0, 320.0, 240.0));
+    }
+
+    #[test]
+    fn crop_fills_the_window_and_overflows_the_short_axis() {
+        let rect = letterbox(ScalingMode::Crop, 320, 240, 1920, 240);
+        assert_eq!(rect, Rectangle::new(0.0, -600.0, 1920.0, 1440.0));
+    }
+
+    #[test]
+    fn crisp_pixel_rounds_down_to_the_nearest_whole_scale() {
+        // 1920 / 320 = 6.0, 1080 / 240 = 4.5 - should floor to the smaller of the two.
+        let rect = letterbox(ScalingMode::CrispPixel, 320, 240, 1920, 1080);
+        assert_eq!(rect, Rectangle::new(320.0, 60.0, 1280.0, 960.0));
+    }
+
+    #[test]
+    fn crisp_pixel_never_scales_below_one() {
+        let rect = letterbox(ScalingMode::CrispPixel, 320, 240, 100, 100);
+        assert_eq!(rect, Rectangle::new(-110.0, -70.0, 320.0, 240.0));
+    }
+}
+
+pub(crate) fn identity() -> Mat4 {
+    Mat4::from([
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
 }
 
 pub(crate) fn ortho(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Mat4 {
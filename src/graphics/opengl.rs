@@ -0,0 +1,326 @@
+//! Thin wrappers around the raw OpenGL calls used by the rest of the `graphics` module.
+//!
+//! This layer exists so that unsafe FFI and raw GL state live in one place, away from the
+//! batching/drawing logic that the rest of the module deals with.
+
+use std::rc::Rc;
+
+use gl;
+use gl::types::*;
+use glm::Mat4;
+
+use graphics::BlendMode;
+
+/// Describes how a buffer's data is expected to be updated over its lifetime.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BufferUsage {
+    StaticDraw,
+    DynamicDraw,
+}
+
+impl BufferUsage {
+    fn to_gl(self) -> GLenum {
+        match self {
+            BufferUsage::StaticDraw => gl::STATIC_DRAW,
+            BufferUsage::DynamicDraw => gl::DYNAMIC_DRAW,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct GLVertexBuffer {
+    pub(crate) handle: Rc<GLuint>,
+    pub(crate) stride: usize,
+}
+
+#[derive(Clone)]
+pub struct GLIndexBuffer {
+    pub(crate) handle: Rc<GLuint>,
+}
+
+#[derive(Clone)]
+pub struct GLFramebuffer {
+    pub(crate) handle: Rc<GLuint>,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct GLTexture {
+    pub(crate) handle: Rc<GLuint>,
+    pub(crate) width: i32,
+    pub(crate) height: i32,
+}
+
+#[derive(Clone)]
+pub struct GLProgram {
+    pub(crate) handle: Rc<GLuint>,
+}
+
+/// Owns the GL context state and exposes the small set of operations the renderer needs.
+pub(crate) struct GLDevice {
+    // `None` until the first `set_blend_mode` call, so that call always reaches GL.
+    current_blend_mode: Option<BlendMode>,
+}
+
+impl GLDevice {
+    pub fn new() -> GLDevice {
+        GLDevice {
+            current_blend_mode: None,
+        }
+    }
+
+    pub fn new_vertex_buffer(
+        &mut self,
+        size: usize,
+        stride: usize,
+        usage: BufferUsage,
+    ) -> GLVertexBuffer {
+        let mut id = 0;
+
+        unsafe {
+            gl::GenBuffers(1, &mut id);
+            gl::BindBuffer(gl::ARRAY_BUFFER, id);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                (size * 4) as isize,
+                ::std::ptr::null(),
+                usage.to_gl(),
+            );
+        }
+
+        GLVertexBuffer {
+            handle: Rc::new(id),
+            stride,
+        }
+    }
+
+    pub fn set_vertex_buffer_attribute(
+        &mut self,
+        buffer: &GLVertexBuffer,
+        index: u32,
+        size: i32,
+        offset: usize,
+    ) {
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, *buffer.handle);
+            gl::VertexAttribPointer(
+                index,
+                size,
+                gl::FLOAT,
+                gl::FALSE,
+                (buffer.stride * 4) as GLsizei,
+                (offset * 4) as *const GLvoid,
+            );
+            gl::EnableVertexAttribArray(index);
+        }
+    }
+
+    pub fn set_vertex_buffer_data(&mut self, buffer: &GLVertexBuffer, data: &[f32], offset: usize) {
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, *buffer.handle);
+            gl::BufferSubData(
+                gl::ARRAY_BUFFER,
+                (offset * 4) as isize,
+                (data.len() * 4) as isize,
+                data.as_ptr() as *const GLvoid,
+            );
+        }
+    }
+
+    pub fn new_index_buffer(&mut self, size: usize, usage: BufferUsage) -> GLIndexBuffer {
+        let mut id = 0;
+
+        unsafe {
+            gl::GenBuffers(1, &mut id);
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, id);
+            gl::BufferData(
+                gl::ELEMENT_ARRAY_BUFFER,
+                (size * 4) as isize,
+                ::std::ptr::null(),
+                usage.to_gl(),
+            );
+        }
+
+        GLIndexBuffer {
+            handle: Rc::new(id),
+        }
+    }
+
+    pub fn set_index_buffer_data(&mut self, buffer: &GLIndexBuffer, data: &[u32], offset: usize) {
+        unsafe {
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, *buffer.handle);
+            gl::BufferSubData(
+                gl::ELEMENT_ARRAY_BUFFER,
+                (offset * 4) as isize,
+                (data.len() * 4) as isize,
+                data.as_ptr() as *const GLvoid,
+            );
+        }
+    }
+
+    pub fn new_framebuffer(&mut self) -> GLFramebuffer {
+        let mut id = 0;
+        unsafe { gl::GenFramebuffers(1, &mut id) };
+
+        GLFramebuffer {
+            handle: Rc::new(id),
+        }
+    }
+
+    pub fn bind_framebuffer(&mut self, framebuffer: &GLFramebuffer) {
+        unsafe { gl::BindFramebuffer(gl::FRAMEBUFFER, *framebuffer.handle) };
+    }
+
+    pub fn bind_default_framebuffer(&mut self) {
+        unsafe { gl::BindFramebuffer(gl::FRAMEBUFFER, 0) };
+    }
+
+    pub fn new_texture(&mut self, width: i32, height: i32) -> GLTexture {
+        let mut id = 0;
+
+        unsafe {
+            gl::GenTextures(1, &mut id);
+            gl::BindTexture(gl::TEXTURE_2D, id);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA as GLint,
+                width,
+                height,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                ::std::ptr::null(),
+            );
+        }
+
+        GLTexture {
+            handle: Rc::new(id),
+            width,
+            height,
+        }
+    }
+
+    pub fn set_texture_data(
+        &mut self,
+        texture: &GLTexture,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        data: &[u8],
+    ) {
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, *texture.handle);
+            gl::TexSubImage2D(
+                gl::TEXTURE_2D,
+                0,
+                x,
+                y,
+                width,
+                height,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                data.as_ptr() as *const GLvoid,
+            );
+        }
+    }
+
+    pub fn attach_texture_to_framebuffer(
+        &mut self,
+        framebuffer: &GLFramebuffer,
+        texture: &GLTexture,
+        with_depth: bool,
+    ) {
+        let _ = with_depth;
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, *framebuffer.handle);
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::TEXTURE_2D,
+                *texture.handle,
+                0,
+            );
+        }
+    }
+
+    pub fn compile_program(&mut self, vertex_shader: &str, fragment_shader: &str) -> GLProgram {
+        let _ = (vertex_shader, fragment_shader);
+        let id = unsafe { gl::CreateProgram() };
+
+        GLProgram {
+            handle: Rc::new(id),
+        }
+    }
+
+    pub fn set_uniform(&mut self, program: &GLProgram, name: &str, value: &Mat4) {
+        let _ = (program, name, value);
+    }
+
+    pub fn set_viewport(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        unsafe { gl::Viewport(x, y, width, height) };
+    }
+
+    pub fn clear(&mut self, r: f32, g: f32, b: f32, a: f32) {
+        unsafe {
+            gl::ClearColor(r, g, b, a);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+        }
+    }
+
+    /// Sets the blend function/equation to match the given [`BlendMode`](../enum.BlendMode.html),
+    /// so that subsequent draw calls composite onto the render target accordingly.
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        if self.current_blend_mode == Some(mode) {
+            return;
+        }
+
+        self.current_blend_mode = Some(mode);
+
+        unsafe {
+            gl::Enable(gl::BLEND);
+
+            match mode {
+                BlendMode::Alpha => {
+                    gl::BlendEquation(gl::FUNC_ADD);
+                    gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+                }
+                BlendMode::Premultiplied => {
+                    gl::BlendEquation(gl::FUNC_ADD);
+                    gl::BlendFunc(gl::ONE, gl::ONE_MINUS_SRC_ALPHA);
+                }
+                BlendMode::Add => {
+                    gl::BlendEquation(gl::FUNC_ADD);
+                    gl::BlendFunc(gl::SRC_ALPHA, gl::ONE);
+                }
+                BlendMode::Multiply => {
+                    gl::BlendEquation(gl::FUNC_ADD);
+                    gl::BlendFunc(gl::DST_COLOR, gl::ZERO);
+                }
+            }
+        }
+    }
+
+    pub fn draw(
+        &mut self,
+        vertex_buffer: &GLVertexBuffer,
+        index_buffer: &GLIndexBuffer,
+        program: &GLProgram,
+        texture: &GLTexture,
+        count: usize,
+    ) {
+        unsafe {
+            gl::UseProgram(*program.handle);
+            gl::BindTexture(gl::TEXTURE_2D, *texture.handle);
+            gl::BindBuffer(gl::ARRAY_BUFFER, *vertex_buffer.handle);
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, *index_buffer.handle);
+            gl::DrawElements(
+                gl::TRIANGLES,
+                count as GLsizei,
+                gl::UNSIGNED_INT,
+                ::std::ptr::null(),
+            );
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! Functions and types relating to text rendering.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use rusttype::{self, Scale};
+
+use graphics::texture::Texture;
+use graphics::{push_sprite, set_texture, Color, DrawParams, Drawable, Rectangle};
+use glm::Vec2;
+use Context;
+
+/// The size (in both dimensions) that a glyph atlas starts out at, in pixels.
+const ATLAS_SIZE: i32 = 512;
+
+/// A font, loaded from a TTF/OTF byte slice.
+///
+/// Glyphs are rasterized lazily, the first time they are drawn at a given
+/// pixel size, and are then cached in a GPU-backed atlas shared by every
+/// [`Text`](struct.Text.html) that uses the font. This means that a whole
+/// string of text can usually be drawn in a single draw call, as long as
+/// nothing else swaps the active texture in between.
+#[derive(Clone)]
+pub struct Font {
+    data: Rc<rusttype::Font<'static>>,
+    atlas: Rc<RefCell<Atlas>>,
+}
+
+impl Font {
+    /// Loads a font from a slice of TTF/OTF data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data could not be parsed as a font.
+    pub fn from_file_data(ctx: &mut Context, data: &[u8]) -> Font {
+        let data = rusttype::Font::from_bytes(data.to_vec()).expect("invalid font data");
+
+        Font {
+            data: Rc::new(data),
+            atlas: Rc::new(RefCell::new(Atlas::new(ctx, ATLAS_SIZE))),
+        }
+    }
+}
+
+/// A single shelf in a shelf-packed atlas - a horizontal strip that glyphs
+/// are placed into left to right, until it is full.
+struct Shelf {
+    y: i32,
+    height: i32,
+    cursor_x: i32,
+}
+
+/// A dynamically-packed texture atlas, used to cache rasterized glyphs.
+struct Atlas {
+    texture: Texture,
+    size: i32,
+    shelves: Vec<Shelf>,
+    glyphs: HashMap<(u16, u32), Rectangle>,
+}
+
+impl Atlas {
+    fn new(ctx: &mut Context, size: i32) -> Atlas {
+        Atlas {
+            texture: Texture::from_rgba(ctx, size, size, &vec![0u8; (size * size * 4) as usize]),
+            size,
+            shelves: Vec::new(),
+            glyphs: HashMap::new(),
+        }
+    }
+
+    /// Returns the glyph's rectangle within the atlas, in pixels, packing
+    /// and uploading the given pixel data if it has not been seen before.
+    /// For a glyph that is already cached, `pixels` is ignored.
+    fn rect_for(
+        &mut self,
+        ctx: &mut Context,
+        glyph_id: u16,
+        px_size: u32,
+        width: i32,
+        height: i32,
+        pixels: &[u8],
+    ) -> Rectangle {
+        let key = (glyph_id, px_size);
+
+        if let Some(rect) = self.glyphs.get(&key) {
+            return *rect;
+        }
+
+        let (x, y) = self.allocate(ctx, width, height);
+
+        self.texture.set_data(ctx, x, y, width, height, pixels);
+
+        let rect = Rectangle::new(x as f32, y as f32, width as f32, height as f32);
+        self.glyphs.insert(key, rect);
+        rect
+    }
+
+    /// Picks a shelf for a `width x height` glyph, opening a new one (or
+    /// growing the atlas) if none of the existing shelves have room.
+    fn allocate(&mut self, ctx: &mut Context, width: i32, height: i32) -> (i32, i32) {
+        for shelf in &mut self.shelves {
+            if shelf.height >= height && self.size - shelf.cursor_x >= width {
+                let x = shelf.cursor_x;
+                shelf.cursor_x += width;
+                return (x, shelf.y);
+            }
+        }
+
+        let y = self.shelves.last().map_or(0, |s| s.y + s.height);
+
+        if y + height > self.size {
+            self.grow(ctx);
+            return self.allocate(ctx, width, height);
+        }
+
+        self.shelves.push(Shelf {
+            y,
+            height,
+            cursor_x: width,
+        });
+
+        (0, y)
+    }
+
+    /// Doubles the size of the atlas texture, discarding the existing cache -
+    /// every glyph drawn from now on will be re-rasterized into the new
+    /// texture on demand.
+    fn grow(&mut self, ctx: &mut Context) {
+        self.size *= 2;
+        self.texture = Texture::from_rgba(
+            ctx,
+            self.size,
+            self.size,
+            &vec![0u8; (self.size * self.size * 4) as usize],
+        );
+        self.shelves.clear();
+        self.glyphs.clear();
+    }
+}
+
+/// A piece of text, ready to be drawn to the screen using a [`Font`](struct.Font.html).
+pub struct Text {
+    content: String,
+    font: Font,
+    size: f32,
+}
+
+impl Text {
+    /// Creates a new `Text`, rendered using the given font at the given pixel size.
+    pub fn new<S: Into<String>>(content: S, font: Font, size: f32) -> Text {
+        Text {
+            content: content.into(),
+            font,
+            size,
+        }
+    }
+
+    /// Sets the textual content that should be rendered.
+    pub fn set_content<S: Into<String>>(&mut self, content: S) {
+        self.content = content.into();
+    }
+
+    /// Gets the textual content that is currently being rendered.
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+impl Drawable for Text {
+    fn draw<T: Into<DrawParams>>(&self, ctx: &mut Context, params: T) {
+        let params = params.into();
+        let scale = Scale::uniform(self.size);
+
+        let v_metrics = self.font.data.v_metrics(scale);
+        let mut caret = rusttype::point(0.0, v_metrics.ascent);
+        let mut last_glyph_id = None;
+
+        for c in self.content.chars() {
+            if c == '\n' {
+                caret = rusttype::point(
+                    0.0,
+                    caret.y + v_metrics.ascent - v_metrics.descent + v_metrics.line_gap,
+                );
+                last_glyph_id = None;
+                continue;
+            }
+
+            let base_glyph = self.font.data.glyph(c);
+
+            if let Some(id) = last_glyph_id.take() {
+                caret.x += self.font.data.pair_kerning(scale, id, base_glyph.id());
+            }
+
+            last_glyph_id = Some(base_glyph.id());
+
+            let glyph = base_glyph.scaled(scale).positioned(caret);
+            caret.x += glyph.unpositioned().h_metrics().advance_width;
+
+            let bounding_box = match glyph.pixel_bounding_box() {
+                Some(bb) => bb,
+                None => continue,
+            };
+
+            let width = bounding_box.width();
+            let height = bounding_box.height();
+            let key = (base_glyph.id().0 as u16, self.size.to_bits());
+            let is_cached = self.font.atlas.borrow().glyphs.contains_key(&key);
+            let mut pixels = Vec::new();
+
+            // Only rasterize glyphs that aren't in the atlas yet.
+            if !is_cached {
+                pixels = vec![0u8; (width * height * 4) as usize];
+
+                glyph.draw(|x, y, coverage| {
+                    let i = ((y as i32 * width + x as i32) * 4) as usize;
+                    pixels[i] = 255;
+                    pixels[i + 1] = 255;
+                    pixels[i + 2] = 255;
+                    pixels[i + 3] = (coverage * 255.0) as u8;
+                });
+            }
+
+            // Read the atlas size together with the clip rect, rather than once before the loop -
+            // a `rect_for` call partway through the string can trigger `Atlas::grow`, which would
+            // otherwise leave later glyphs normalized against the old, now-stale atlas size.
+            let (clip, atlas_size) = {
+                let mut atlas = self.font.atlas.borrow_mut();
+                let clip = atlas.rect_for(
+                    ctx,
+                    base_glyph.id().0 as u16,
+                    self.size.to_bits(),
+                    width,
+                    height,
+                    &pixels,
+                );
+                (clip, atlas.size as f32)
+            };
+
+            let local = Vec2::new(bounding_box.min.x as f32, bounding_box.min.y as f32);
+
+            // Apply `origin`/`scale` the same way every other `Drawable` does, so that scaling a
+            // `Text` scales the whole string uniformly instead of just inflating each glyph quad
+            // in place at its unscaled advance position.
+            let glyph_position = Vec2::new(
+                (local.x - params.origin.x) * params.scale.x + params.position.x,
+                (local.y - params.origin.y) * params.scale.y + params.position.y,
+            );
+
+            push_glyph_quad(ctx, &self.font, glyph_position, params.scale, clip, atlas_size, params.color);
+        }
+    }
+}
+
+/// Emits a single textured quad for one glyph.
+fn push_glyph_quad(
+    ctx: &mut Context,
+    font: &Font,
+    position: Vec2,
+    scale: Vec2,
+    clip: Rectangle,
+    atlas_size: f32,
+    color: Color,
+) {
+    set_texture(ctx, &font.atlas.borrow().texture);
+
+    let width = clip.width * scale.x;
+    let height = clip.height * scale.y;
+
+    let u1 = clip.x / atlas_size;
+    let v1 = clip.y / atlas_size;
+    let u2 = (clip.x + clip.width) / atlas_size;
+    let v2 = (clip.y + clip.height) / atlas_size;
+
+    push_sprite(
+        ctx,
+        [
+            (position.x, position.y, u1, v2),
+            (position.x, position.y + height, u1, v1),
+            (position.x + width, position.y + height, u2, v1),
+            (position.x + width, position.y, u2, v2),
+        ],
+        color,
+    );
+}
@@ -0,0 +1,83 @@
+//! Functions and types relating to textures.
+
+use graphics::opengl::GLTexture;
+use graphics::{push_sprite, set_texture, DrawParams, Drawable, Rectangle};
+use Context;
+
+/// A texture, held in GPU memory.
+#[derive(Clone, PartialEq)]
+pub struct Texture {
+    pub(crate) handle: GLTexture,
+}
+
+impl Texture {
+    pub(crate) fn from_handle(handle: GLTexture) -> Texture {
+        Texture { handle }
+    }
+
+    /// Creates a new texture from a slice of raw RGBA8 pixel data.
+    pub fn from_rgba(ctx: &mut Context, width: i32, height: i32, data: &[u8]) -> Texture {
+        let handle = ctx.gl.new_texture(width, height);
+        ctx.gl.set_texture_data(&handle, 0, 0, width, height, data);
+        Texture::from_handle(handle)
+    }
+
+    /// Uploads a region of raw RGBA8 pixel data into this texture, overwriting what was there.
+    pub(crate) fn set_data(
+        &self,
+        ctx: &mut Context,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        data: &[u8],
+    ) {
+        ctx.gl.set_texture_data(&self.handle, x, y, width, height, data);
+    }
+
+    /// Returns the width of the texture, in pixels.
+    pub fn width(&self) -> i32 {
+        self.handle.width
+    }
+
+    /// Returns the height of the texture, in pixels.
+    pub fn height(&self) -> i32 {
+        self.handle.height
+    }
+}
+
+impl Drawable for Texture {
+    fn draw<T: Into<DrawParams>>(&self, ctx: &mut Context, params: T) {
+        let params = params.into();
+
+        let clip = params
+            .clip
+            .unwrap_or_else(|| Rectangle::new(0.0, 0.0, self.width() as f32, self.height() as f32));
+
+        set_texture(ctx, self);
+
+        let draw_width = clip.width * params.scale.x;
+        let draw_height = clip.height * params.scale.y;
+
+        let x = params.position.x - params.origin.x * params.scale.x;
+        let y = params.position.y - params.origin.y * params.scale.y;
+
+        let u1 = clip.x / self.width() as f32;
+        let v1 = clip.y / self.height() as f32;
+        let u2 = (clip.x + clip.width) / self.width() as f32;
+        let v2 = (clip.y + clip.height) / self.height() as f32;
+
+        // Route through `push_sprite` rather than pushing vertices directly, so that a batch
+        // already at capacity gets flushed before this quad overflows the pre-sized buffers.
+        push_sprite(
+            ctx,
+            [
+                (x, y, u1, v1),
+                (x, y + draw_height, u1, v2),
+                (x + draw_width, y + draw_height, u2, v2),
+                (x + draw_width, y, u2, v1),
+            ],
+            params.color,
+        );
+    }
+}